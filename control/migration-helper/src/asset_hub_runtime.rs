@@ -0,0 +1,123 @@
+#[cfg(feature = "rococo")]
+pub use asset_hub_rococo_runtime::*;
+
+#[cfg(feature = "polkadot")]
+pub use asset_hub_polkadot_runtime::*;
+
+#[cfg(feature = "westend")]
+pub use asset_hub_westend_runtime::*;
+
+#[cfg(feature = "paseo")]
+pub use asset_hub_paseo_runtime::*;
+
+pub type AssetId = foreign_assets::storage::types::asset::Param0;
+
+/// Returns the ERC-20 contract of a foreign asset bridged from the Ethereum chain `chain_id`, or
+/// `None` for any other asset.
+#[cfg(any(feature = "rococo", feature = "polkadot", feature = "paseo"))]
+pub fn ethereum_token(chain_id: u64, id: &AssetId) -> Option<[u8; 20]> {
+    use runtime_types::xcm::v3::{
+        junction::Junction::AccountKey20, junction::Junction::GlobalConsensus, junction::NetworkId,
+        junctions::Junctions::X2,
+    };
+    match (id.parents, &id.interior) {
+        (
+            2,
+            X2(GlobalConsensus(NetworkId::Ethereum { chain_id: id }), AccountKey20 { key, .. }),
+        ) if *id == chain_id => Some(*key),
+        _ => None,
+    }
+}
+
+/// Returns the ERC-20 contract of a foreign asset bridged from the Ethereum chain `chain_id`, or
+/// `None` for any other asset.
+#[cfg(feature = "westend")]
+pub fn ethereum_token(chain_id: u64, id: &AssetId) -> Option<[u8; 20]> {
+    use runtime_types::staging_xcm::v4::{
+        junction::Junction::AccountKey20, junction::Junction::GlobalConsensus, junction::NetworkId,
+        junctions::Junctions::X2,
+    };
+    match (id.parents, &id.interior) {
+        (
+            2,
+            X2([GlobalConsensus(NetworkId::Ethereum { chain_id: id }), AccountKey20 { key, .. }]),
+        ) if *id == chain_id => Some(*key),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: [u8; 20] = [0x11; 20];
+
+    #[cfg(any(feature = "rococo", feature = "polkadot", feature = "paseo"))]
+    use runtime_types::xcm::v3::{
+        junction::Junction::AccountKey20, junction::Junction::GlobalConsensus, junction::NetworkId,
+        junctions::Junctions::X1, junctions::Junctions::X2,
+    };
+
+    #[cfg(any(feature = "rococo", feature = "polkadot", feature = "paseo"))]
+    fn erc20(chain_id: u64, key: [u8; 20]) -> AssetId {
+        AssetId {
+            parents: 2,
+            interior: X2(
+                GlobalConsensus(NetworkId::Ethereum { chain_id }),
+                AccountKey20 { network: None, key },
+            ),
+        }
+    }
+
+    #[cfg(any(feature = "rococo", feature = "polkadot", feature = "paseo"))]
+    fn ether(chain_id: u64) -> AssetId {
+        AssetId {
+            parents: 2,
+            interior: X1(GlobalConsensus(NetworkId::Ethereum { chain_id })),
+        }
+    }
+
+    #[cfg(feature = "westend")]
+    use runtime_types::staging_xcm::v4::{
+        junction::Junction::AccountKey20, junction::Junction::GlobalConsensus, junction::NetworkId,
+        junctions::Junctions::X1, junctions::Junctions::X2,
+    };
+
+    #[cfg(feature = "westend")]
+    fn erc20(chain_id: u64, key: [u8; 20]) -> AssetId {
+        AssetId {
+            parents: 2,
+            interior: X2([
+                GlobalConsensus(NetworkId::Ethereum { chain_id }),
+                AccountKey20 { network: None, key },
+            ]),
+        }
+    }
+
+    #[cfg(feature = "westend")]
+    fn ether(chain_id: u64) -> AssetId {
+        AssetId {
+            parents: 2,
+            interior: X1([GlobalConsensus(NetworkId::Ethereum { chain_id })]),
+        }
+    }
+
+    #[test]
+    fn ethereum_token_returns_erc20_on_chain() {
+        assert_eq!(ethereum_token(1, &erc20(1, TOKEN)), Some(TOKEN));
+    }
+
+    #[test]
+    fn ethereum_token_ignores_other_chain() {
+        assert_eq!(ethereum_token(1, &erc20(11155111, TOKEN)), None);
+    }
+
+    #[test]
+    fn ethereum_token_ignores_non_erc20_location() {
+        assert_eq!(ethereum_token(1, &ether(1)), None);
+
+        let mut token = erc20(1, TOKEN);
+        token.parents = 1;
+        assert_eq!(ethereum_token(1, &token), None);
+    }
+}
@@ -0,0 +1,11 @@
+#[cfg(feature = "rococo")]
+pub use bridge_hub_rococo_runtime::*;
+
+#[cfg(feature = "polkadot")]
+pub use bridge_hub_polkadot_runtime::*;
+
+#[cfg(feature = "westend")]
+pub use bridge_hub_westend_runtime::*;
+
+#[cfg(feature = "paseo")]
+pub use bridge_hub_paseo_runtime::*;
@@ -0,0 +1,274 @@
+use alloy_primitives::{keccak256, Address, FixedBytes, U256};
+use subxt::backend::rpc::{rpc_params, RpcClient};
+
+/// Read-only view of the Gateway contract and the tokens it locks, queried with `eth_call`
+/// against the latest block unless a block number is given.
+pub struct Gateway {
+    client: RpcClient,
+    address: Address,
+}
+
+impl Gateway {
+    pub async fn new(url: &str, address: Address) -> Result<Self, Box<dyn std::error::Error>> {
+        let client = RpcClient::from_url(url).await?;
+        Ok(Gateway { client, address })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub async fn chain_id(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let chain_id: String = self.client.request("eth_chainId", rpc_params![]).await?;
+        Ok(u64::from_str_radix(chain_id.trim_start_matches("0x"), 16)?)
+    }
+
+    /// Returns whether there is a contract deployed at `address`.
+    pub async fn has_code(&self, address: Address) -> Result<bool, Box<dyn std::error::Error>> {
+        let code: String = self
+            .client
+            .request("eth_getCode", rpc_params![address.to_string(), "latest"])
+            .await?;
+        Ok(!hex::decode(code.trim_start_matches("0x"))?.is_empty())
+    }
+
+    /// Returns the `(inbound, outbound)` nonces of a channel at `block`, or `None` if the channel
+    /// does not exist on the Gateway. Blocks before the Gateway was deployed have no contract at
+    /// its address, so the call returns no data and the channel is treated as not existing yet.
+    pub async fn channel_nonces_of(
+        &self,
+        channel_id: [u8; 32],
+        block: Option<u64>,
+    ) -> Result<Option<(u64, u64)>, Box<dyn std::error::Error>> {
+        match self
+            .call(self.address, "channelNoncesOf(bytes32)", &channel_id, block)
+            .await?
+        {
+            Ok(output) if output.is_empty() => Ok(None),
+            Ok(output) => Ok(Some((decode_u64(&output, 0)?, decode_u64(&output, 1)?))),
+            Err(revert) if revert == selector("ChannelDoesNotExist()") => Ok(None),
+            Err(revert) => Err(unexpected_revert("channelNoncesOf", &revert)),
+        }
+    }
+
+    /// Returns the address of an agent, or `None` if the agent does not exist on the Gateway.
+    pub async fn agent_of(
+        &self,
+        agent_id: [u8; 32],
+    ) -> Result<Option<Address>, Box<dyn std::error::Error>> {
+        match self
+            .call(self.address, "agentOf(bytes32)", &agent_id, None)
+            .await?
+        {
+            Ok(output) => Ok(Some(Address::from_slice(&word(&output, 0)?[12..]))),
+            Err(revert) if revert == selector("AgentDoesNotExist()") => Ok(None),
+            Err(revert) => Err(unexpected_revert("agentOf", &revert)),
+        }
+    }
+
+    /// Returns the balance of `owner` in the ERC-20 contract `token`. Returns why the balance
+    /// could not be read as `Err` if the call reverted or returned too little data, as any
+    /// contract can be registered as a token.
+    pub async fn balance_of(
+        &self,
+        token: Address,
+        owner: Address,
+    ) -> Result<Result<U256, String>, Box<dyn std::error::Error>> {
+        match self
+            .call(token, "balanceOf(address)", &owner.into_word().0, None)
+            .await?
+        {
+            Ok(output) => Ok(word(&output, 0)
+                .map(|word| U256::from_be_bytes(word.0))
+                .map_err(|_| format!("returned {} bytes", output.len()))),
+            Err(revert) => Ok(Err(format!("reverted with 0x{}", hex::encode(revert)))),
+        }
+    }
+
+    /// Returns the `(channel_id, nonce, message_id)` of every `OutboundMessageAccepted` event
+    /// emitted by the Gateway in blocks `from..=to`.
+    pub async fn outbound_messages(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<([u8; 32], u64, [u8; 32])>, Box<dyn std::error::Error>> {
+        let filter = serde_json::json!({
+            "address": self.address.to_string(),
+            "topics": [keccak256("OutboundMessageAccepted(bytes32,uint64,bytes32,bytes)").to_string()],
+            "fromBlock": block_tag(Some(from)),
+            "toBlock": block_tag(Some(to)),
+        });
+        let logs: Vec<serde_json::Value> = self
+            .client
+            .request("eth_getLogs", rpc_params![filter])
+            .await?;
+
+        let mut messages = vec![];
+        for log in logs {
+            // The channel and message ids are indexed, the nonce is the first word of the data.
+            let topics = log["topics"]
+                .as_array()
+                .ok_or("eth_getLogs returned a log without topics")?;
+            let topic = |index: usize| -> Result<[u8; 32], Box<dyn std::error::Error>> {
+                let topic = topics.get(index).and_then(|topic| topic.as_str()).ok_or(
+                    "eth_getLogs returned an OutboundMessageAccepted log with missing topics",
+                )?;
+                Ok(hex::decode(topic.trim_start_matches("0x"))?[..]
+                    .try_into()
+                    .map_err(|_| "eth_getLogs returned a topic that is not 32 bytes")?)
+            };
+            let data = log["data"]
+                .as_str()
+                .ok_or("eth_getLogs returned a log without data")?;
+            let nonce = decode_u64(&hex::decode(data.trim_start_matches("0x"))?, 0)?;
+            messages.push((topic(1)?, nonce, topic(2)?));
+        }
+        Ok(messages)
+    }
+
+    /// Calls a function of the contract at `to` taking a single 32-byte argument. Returns the revert data as `Err` if
+    /// the call reverted, so that callers can tell known contract errors apart from RPC failures.
+    async fn call(
+        &self,
+        to: Address,
+        signature: &str,
+        arg: &[u8; 32],
+        block: Option<u64>,
+    ) -> Result<Result<Vec<u8>, Vec<u8>>, Box<dyn std::error::Error>> {
+        let mut data = selector(signature);
+        data.extend_from_slice(arg);
+
+        let request = serde_json::json!({
+            "to": to.to_string(),
+            "data": format!("0x{}", hex::encode(data)),
+        });
+        match self
+            .client
+            .request::<String>("eth_call", rpc_params![request, block_tag(block)])
+            .await
+        {
+            Ok(output) => Ok(Ok(hex::decode(output.trim_start_matches("0x"))?)),
+            Err(err) => match revert_data(&err) {
+                Some(revert) => Ok(Err(revert)),
+                None => Err(err.into()),
+            },
+        }
+    }
+}
+
+fn block_tag(block: Option<u64>) -> String {
+    match block {
+        Some(number) => format!("{number:#x}"),
+        None => "latest".to_owned(),
+    }
+}
+
+fn selector(signature: &str) -> Vec<u8> {
+    keccak256(signature.as_bytes())[..4].to_vec()
+}
+
+/// Extracts the revert data from a JSON-RPC error returned by `eth_call`. Execution nodes report
+/// a revert as an error object whose `data` is the hex-encoded return data of the call.
+fn revert_data(err: &subxt::Error) -> Option<Vec<u8>> {
+    let subxt::Error::Rpc(subxt::error::RpcError::ClientError(err)) = err else {
+        return None;
+    };
+    let jsonrpsee_core::ClientError::Call(err) = err.downcast_ref()? else {
+        return None;
+    };
+    parse_revert_data(err.data()?.get())
+}
+
+/// Decodes the raw JSON `data` of an `eth_call` error, a hex string, into the revert data.
+fn parse_revert_data(data: &str) -> Option<Vec<u8>> {
+    let data: String = serde_json::from_str(data).ok()?;
+    hex::decode(data.trim_start_matches("0x")).ok()
+}
+
+fn unexpected_revert(function: &str, revert: &[u8]) -> Box<dyn std::error::Error> {
+    format!("{function} reverted with 0x{}", hex::encode(revert)).into()
+}
+
+fn word(output: &[u8], index: usize) -> Result<FixedBytes<32>, String> {
+    output
+        .get(index * 32..(index + 1) * 32)
+        .map(FixedBytes::from_slice)
+        .ok_or_else(|| {
+            format!(
+                "eth_call returned {} bytes, expected at least {}",
+                output.len(),
+                (index + 1) * 32
+            )
+        })
+}
+
+fn decode_u64(output: &[u8], index: usize) -> Result<u64, String> {
+    let word = word(output, index)?;
+    if word[..24].iter().any(|b| *b != 0) {
+        return Err("eth_call returned a value that does not fit in uint64".to_owned());
+    }
+    Ok(u64::from_be_bytes(word[24..].try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_tag_is_hex_number_or_latest() {
+        assert_eq!(block_tag(Some(20_000_000)), "0x1312d00");
+        assert_eq!(block_tag(Some(0)), "0x0");
+        assert_eq!(block_tag(None), "latest");
+    }
+
+    #[test]
+    fn word_rejects_short_output() {
+        let output = [0u8; 63];
+        assert!(word(&output, 0).is_ok());
+        assert_eq!(
+            word(&output, 1).unwrap_err(),
+            "eth_call returned 63 bytes, expected at least 64"
+        );
+        assert_eq!(
+            word(&[], 0).unwrap_err(),
+            "eth_call returned 0 bytes, expected at least 32"
+        );
+    }
+
+    #[test]
+    fn decode_u64_reads_second_word() {
+        let mut output = [0u8; 64];
+        output[63] = 7;
+        output[56] = 1;
+        assert_eq!(decode_u64(&output, 0), Ok(0));
+        assert_eq!(decode_u64(&output, 1), Ok((1 << 56) + 7));
+    }
+
+    #[test]
+    fn decode_u64_rejects_overflow() {
+        let mut output = [0u8; 32];
+        output[23] = 1;
+        assert!(decode_u64(&output, 0).is_err());
+
+        let mut output = [0u8; 32];
+        output[24..].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert_eq!(decode_u64(&output, 0), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn parse_revert_data_decodes_channel_does_not_exist() {
+        let revert = selector("ChannelDoesNotExist()");
+        let data = format!("\"0x{}\"", hex::encode(&revert));
+        assert_eq!(parse_revert_data(&data), Some(revert));
+        assert_ne!(
+            parse_revert_data(&data),
+            Some(selector("AgentDoesNotExist()"))
+        );
+    }
+
+    #[test]
+    fn parse_revert_data_rejects_non_hex() {
+        assert_eq!(parse_revert_data("\"execution reverted\""), None);
+        assert_eq!(parse_revert_data("null"), None);
+    }
+}
@@ -0,0 +1,435 @@
+mod asset_hub_runtime;
+mod bridge_hub_runtime;
+mod constants;
+mod ethereum;
+
+use alloy_primitives::{Address, U256};
+use clap::Parser;
+use codec::{Decode, DecodeAll};
+use constants::{ASSET_HUB_API, ASSET_HUB_ID, BRIDGE_HUB_API};
+use ethereum::Gateway;
+use std::{collections::BTreeMap, error::Error, ops::RangeInclusive, str::FromStr};
+use subxt::{
+    backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+    utils::H256,
+    OnlineClient, PolkadotConfig,
+};
+
+use crate::bridge_hub_runtime::{
+    ethereum_inbound_queue::events::MessageReceived, runtime_types::snowbridge_core::ChannelId,
+};
+
+/// Audit the consistency of bridge state between the Ethereum Gateway, BridgeHub and AssetHub.
+#[derive(Debug, Parser)]
+#[command(name = "snowbridge-migration-helper", version, about, long_about = None)]
+struct Cli {
+    /// WebSocket endpoint of an Ethereum execution node
+    #[arg(long, value_name = "URL")]
+    eth_rpc: String,
+
+    /// WebSocket endpoint of a BridgeHub node. Defaults to a public endpoint of the network.
+    #[arg(long, value_name = "URL")]
+    parachain_rpc: Option<String>,
+
+    /// WebSocket endpoint of an AssetHub node. Defaults to a public endpoint of the network.
+    #[arg(long, value_name = "URL")]
+    asset_hub_rpc: Option<String>,
+
+    /// Address of the Gateway contract. Defaults to the address stored on BridgeHub.
+    #[arg(long, value_name = "ADDRESS", value_parser=parse_eth_address_without_validation)]
+    gateway_address: Option<Address>,
+
+    /// First Ethereum block to scan for sent messages. Messages are only checked if all four
+    /// block range options are given.
+    #[arg(long, value_name = "BLOCK", requires_all = ["eth_to_block", "bridge_hub_from_block", "bridge_hub_to_block"])]
+    eth_from_block: Option<u64>,
+
+    /// Last Ethereum block to scan for sent messages
+    #[arg(long, value_name = "BLOCK", requires = "eth_from_block")]
+    eth_to_block: Option<u64>,
+
+    /// First BridgeHub block to scan for received messages
+    #[arg(long, value_name = "BLOCK", requires = "eth_from_block")]
+    bridge_hub_from_block: Option<u32>,
+
+    /// Last BridgeHub block to scan for received messages
+    #[arg(long, value_name = "BLOCK", requires = "eth_from_block")]
+    bridge_hub_to_block: Option<u32>,
+}
+
+/// Length of the hash that `Twox64Concat`, used by `EthereumSystem::Channels`, puts before a key.
+const TWOX_64_HASH_LEN: usize = 8;
+
+/// Length of the hash that `Blake2_128Concat`, used by `ForeignAssets::Asset`, puts before a key.
+const BLAKE2_128_HASH_LEN: usize = 16;
+
+/// Decodes the key of a storage map entry whose hasher appends the key to a `hash_len` byte hash.
+fn decode_map_key<K: Decode>(key_bytes: &[u8], hash_len: usize) -> Result<K, Box<dyn Error>> {
+    // The pallet and storage item prefixes are 16 bytes each.
+    let mut key = key_bytes
+        .get(32 + hash_len..)
+        .ok_or_else(|| format!("storage key 0x{} is too short", hex::encode(key_bytes)))?;
+    Ok(K::decode_all(&mut key)?)
+}
+
+fn parse_eth_address_without_validation(v: &str) -> Result<Address, String> {
+    Address::from_str(v).map_err(|_| "invalid ethereum address".to_owned())
+}
+
+#[tokio::main]
+async fn main() {
+    match run().await {
+        Ok(issues) if issues.is_empty() => {
+            println!("No inconsistencies found");
+        }
+        Ok(issues) => {
+            println!("Found {} inconsistencies:", issues.len());
+            for issue in issues {
+                println!("  {issue}");
+            }
+            std::process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    }
+}
+
+async fn run() -> Result<Vec<String>, Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    let rpc = RpcClient::from_url(cli.parachain_rpc.unwrap_or(BRIDGE_HUB_API.to_owned())).await?;
+    let api: OnlineClient<PolkadotConfig> = OnlineClient::from_rpc_client(rpc.clone()).await?;
+    let storage = api.storage().at_latest().await?;
+
+    let gateway_address = match cli.gateway_address {
+        Some(address) => address,
+        None => {
+            let key = sp_crypto_hashing::twox_128(b":EthereumGatewayAddress:").to_vec();
+            let value = storage
+                .fetch_raw(key)
+                .await?
+                .ok_or("Gateway address is not set on BridgeHub, pass --gateway-address")?;
+            Address::from(<[u8; 20]>::decode(&mut value.as_slice())?)
+        }
+    };
+    let gateway = Gateway::new(&cli.eth_rpc, gateway_address).await?;
+
+    let mut issues = vec![];
+
+    let chain_id = gateway.chain_id().await?;
+    if chain_id != bridge_hub_runtime::CHAIN_ID {
+        issues.push(format!(
+            "Ethereum chain id is {chain_id}, BridgeHub is configured for {}",
+            bridge_hub_runtime::CHAIN_ID
+        ));
+    }
+
+    // Without a contract every eth_call returns empty data, so there is nothing else to compare.
+    if !gateway.has_code(gateway.address()).await? {
+        issues.push(format!(
+            "no contract at Gateway address {}",
+            gateway.address()
+        ));
+        return Ok(issues);
+    }
+
+    println!("Gateway: {}", gateway.address());
+
+    let mut channel_ids = vec![];
+    let mut asset_hub_agent = None;
+
+    let mut channels = storage
+        .iter(
+            bridge_hub_runtime::storage()
+                .ethereum_system()
+                .channels_iter(),
+        )
+        .await?;
+    while let Some(entry) = channels.next().await {
+        let entry = entry?;
+        let ChannelId(channel_id) = decode_map_key(&entry.key_bytes, TWOX_64_HASH_LEN)?;
+        let channel = entry.value;
+        let label = format!(
+            "Channel 0x{} (para {})",
+            hex::encode(channel_id),
+            channel.para_id.0
+        );
+        println!("{label}");
+
+        let inbound_nonce = storage
+            .fetch_or_default(
+                &bridge_hub_runtime::storage()
+                    .ethereum_inbound_queue()
+                    .nonce(ChannelId(channel_id)),
+            )
+            .await?;
+        let outbound_nonce = storage
+            .fetch_or_default(
+                &bridge_hub_runtime::storage()
+                    .ethereum_outbound_queue()
+                    .nonce(ChannelId(channel_id)),
+            )
+            .await?;
+
+        match gateway.channel_nonces_of(channel_id, None).await? {
+            Some((gateway_inbound_nonce, gateway_outbound_nonce)) => {
+                channel_ids.push(channel_id);
+                println!(
+                    "  Ethereum -> Polkadot: gateway nonce {gateway_outbound_nonce}, inbound queue nonce {inbound_nonce}"
+                );
+                println!(
+                    "  Polkadot -> Ethereum: outbound queue nonce {outbound_nonce}, gateway nonce {gateway_inbound_nonce}"
+                );
+                // Messages are only ever delivered after they are sent, so the receiving side
+                // may lag behind but never run ahead.
+                if inbound_nonce > gateway_outbound_nonce {
+                    issues.push(format!(
+                        "{label}: inbound queue nonce {inbound_nonce} is ahead of gateway outbound nonce {gateway_outbound_nonce}"
+                    ));
+                }
+                if gateway_inbound_nonce > outbound_nonce {
+                    issues.push(format!(
+                        "{label}: gateway inbound nonce {gateway_inbound_nonce} is ahead of outbound queue nonce {outbound_nonce}"
+                    ));
+                }
+            }
+            None => {
+                issues.push(format!("{label}: not found on the Gateway"));
+            }
+        }
+
+        let agent_id = channel.agent_id;
+        let agent_registered = storage
+            .fetch(
+                &bridge_hub_runtime::storage()
+                    .ethereum_system()
+                    .agents(agent_id),
+            )
+            .await?
+            .is_some();
+        if !agent_registered {
+            issues.push(format!(
+                "{label}: agent {agent_id:?} is not registered on BridgeHub"
+            ));
+        }
+        match gateway.agent_of(agent_id.0).await? {
+            Some(agent) => {
+                println!("  Agent {agent_id:?}: {agent}");
+                if channel.para_id.0 == ASSET_HUB_ID {
+                    asset_hub_agent = Some(agent);
+                }
+            }
+            None => {
+                issues.push(format!(
+                    "{label}: agent {agent_id:?} not found on the Gateway"
+                ));
+            }
+        }
+    }
+
+    match (
+        cli.eth_from_block,
+        cli.eth_to_block,
+        cli.bridge_hub_from_block,
+        cli.bridge_hub_to_block,
+    ) {
+        (Some(eth_from), Some(eth_to), Some(bridge_hub_from), Some(bridge_hub_to)) => {
+            if eth_from > eth_to || bridge_hub_from > bridge_hub_to {
+                return Err("block ranges must not end before they start".into());
+            }
+            check_messages(
+                &api,
+                &LegacyRpcMethods::new(rpc),
+                &gateway,
+                &channel_ids,
+                eth_from..=eth_to,
+                bridge_hub_from..=bridge_hub_to,
+                &mut issues,
+            )
+            .await?;
+        }
+        _ => println!("No block ranges given, messages not checked"),
+    }
+
+    let Some(asset_hub_agent) = asset_hub_agent else {
+        issues.push(format!(
+            "no agent found for AssetHub (para {ASSET_HUB_ID}), token supplies not checked"
+        ));
+        return Ok(issues);
+    };
+
+    let asset_hub_api: OnlineClient<PolkadotConfig> =
+        OnlineClient::from_url(cli.asset_hub_rpc.unwrap_or(ASSET_HUB_API.to_owned())).await?;
+    let mut assets = asset_hub_api
+        .storage()
+        .at_latest()
+        .await?
+        .iter(asset_hub_runtime::storage().foreign_assets().asset_iter())
+        .await?;
+    while let Some(entry) = assets.next().await {
+        let entry = entry?;
+        let asset_id: asset_hub_runtime::AssetId =
+            decode_map_key(&entry.key_bytes, BLAKE2_128_HASH_LEN)?;
+        let Some(token) =
+            asset_hub_runtime::ethereum_token(bridge_hub_runtime::CHAIN_ID, &asset_id)
+        else {
+            continue;
+        };
+        let token = Address::from(token);
+        let label = format!("Token {token}");
+
+        if !gateway.has_code(token).await? {
+            issues.push(format!("{label}: no contract at token address"));
+            continue;
+        }
+
+        let supply = entry.value.supply;
+        // Anyone can register any contract as a token, so a broken one is reported rather than
+        // aborting the audit.
+        let locked = match gateway.balance_of(token, asset_hub_agent).await? {
+            Ok(locked) => locked,
+            Err(reason) => {
+                issues.push(format!("{label}: balanceOf {reason}"));
+                continue;
+            }
+        };
+        println!("{label}: AssetHub supply {supply}, locked in agent {locked}");
+        // Tokens can be sent to the agent directly, so more may be locked than was minted.
+        if U256::from(supply) > locked {
+            issues.push(format!(
+                "{label}: AssetHub supply {supply} exceeds the {locked} locked in agent {asset_hub_agent}"
+            ));
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Compares the messages sent by the Gateway in a range of Ethereum blocks with the messages
+/// received by the inbound queue in a range of BridgeHub blocks.
+///
+/// The two ranges never line up exactly, so for each channel only the nonces sent in the
+/// Ethereum range and received in the BridgeHub range are compared. Messages sent but not yet
+/// received in the BridgeHub range are still in flight and are not reported.
+async fn check_messages(
+    api: &OnlineClient<PolkadotConfig>,
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    gateway: &Gateway,
+    channel_ids: &[[u8; 32]],
+    eth_blocks: RangeInclusive<u64>,
+    bridge_hub_blocks: RangeInclusive<u32>,
+    issues: &mut Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let mut sent = BTreeMap::new();
+    for (channel_id, nonce, message_id) in gateway
+        .outbound_messages(*eth_blocks.start(), *eth_blocks.end())
+        .await?
+    {
+        if !channel_ids.contains(&channel_id) {
+            issues.push(format!(
+                "Message {nonce} (0x{}) was sent on channel 0x{} which is not registered on BridgeHub",
+                hex::encode(message_id),
+                hex::encode(channel_id)
+            ));
+        }
+        sent.insert((channel_id, nonce), message_id);
+    }
+
+    let mut received = BTreeMap::new();
+    for number in bridge_hub_blocks.clone() {
+        let hash = bridge_hub_block_hash(rpc, number).await?;
+        let events = api.blocks().at(hash).await?.events().await?;
+        for event in events.find::<MessageReceived>() {
+            let event = event?;
+            received.insert((event.channel_id.0, event.nonce), event.message_id);
+        }
+    }
+
+    println!(
+        "Ethereum blocks {eth_blocks:?}: {} messages sent, BridgeHub blocks {bridge_hub_blocks:?}: {} messages received",
+        sent.len(),
+        received.len()
+    );
+
+    for channel_id in channel_ids {
+        let label = format!("Channel 0x{}", hex::encode(channel_id));
+
+        // Nonces are sequential, so the nonces before and at the end of each range bound the
+        // messages the range must contain. A channel that does not exist yet, including before
+        // the Gateway was deployed, has sent nothing.
+        let gateway_nonce = |block| async move {
+            Ok::<_, Box<dyn Error>>(
+                gateway
+                    .channel_nonces_of(*channel_id, Some(block))
+                    .await?
+                    .map_or(0, |(_, outbound)| outbound),
+            )
+        };
+        let inbound_queue_nonce = |block| async move {
+            let hash = bridge_hub_block_hash(rpc, block).await?;
+            Ok::<_, Box<dyn Error>>(
+                api.storage()
+                    .at(hash)
+                    .fetch_or_default(
+                        &bridge_hub_runtime::storage()
+                            .ethereum_inbound_queue()
+                            .nonce(ChannelId(*channel_id)),
+                    )
+                    .await?,
+            )
+        };
+        let sent_nonces = match *eth_blocks.start() {
+            0 => 1,
+            start => gateway_nonce(start - 1).await? + 1,
+        }..=gateway_nonce(*eth_blocks.end()).await?;
+        let received_nonces = match *bridge_hub_blocks.start() {
+            0 => 1,
+            start => inbound_queue_nonce(start - 1).await? + 1,
+        }..=inbound_queue_nonce(*bridge_hub_blocks.end()).await?;
+
+        for nonce in sent_nonces.clone() {
+            let Some(message_id) = sent.get(&(*channel_id, nonce)) else {
+                issues.push(format!(
+                    "{label}: no OutboundMessageAccepted event for nonce {nonce} in Ethereum blocks {eth_blocks:?}"
+                ));
+                continue;
+            };
+            if !received_nonces.contains(&nonce) {
+                continue;
+            }
+            match received.get(&(*channel_id, nonce)) {
+                Some(received_id) if received_id == message_id => {}
+                Some(received_id) => issues.push(format!(
+                    "{label}: message {nonce} was sent as 0x{} but received as 0x{}",
+                    hex::encode(message_id),
+                    hex::encode(received_id)
+                )),
+                None => issues.push(format!(
+                    "{label}: message {nonce} (0x{}) was not received in BridgeHub blocks {bridge_hub_blocks:?}",
+                    hex::encode(message_id)
+                )),
+            }
+        }
+        for nonce in received_nonces {
+            if !received.contains_key(&(*channel_id, nonce)) {
+                issues.push(format!(
+                    "{label}: no MessageReceived event for nonce {nonce} in BridgeHub blocks {bridge_hub_blocks:?}"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn bridge_hub_block_hash(
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    number: u32,
+) -> Result<H256, Box<dyn Error>> {
+    Ok(rpc
+        .chain_get_block_hash(Some(number.into()))
+        .await?
+        .ok_or_else(|| format!("BridgeHub block {number} not found"))?)
+}